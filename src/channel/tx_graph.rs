@@ -14,10 +14,18 @@
 //! The module must be used only by libraries providing new channel types and
 //! not by the final LN node implementations.
 
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
 use std::str::FromStr;
 
-use bitcoin::{OutPoint, Transaction, TxIn, TxOut};
+use bitcoin::secp256k1::{Secp256k1, Verification};
+use bitcoin::util::bip32::KeySource;
+use bitcoin::util::taproot::{LeafVersion, TaprootBuilder, TaprootSpendInfo};
+use bitcoin::{
+    OutPoint, PackedLockTime, Script, Sequence, Transaction, TxIn, TxOut, Txid,
+    XOnlyPublicKey,
+};
 use wallet::psbt::{self, Psbt, PsbtVersion};
 
 use super::bolt::TxType;
@@ -29,14 +37,223 @@ pub trait TxIndex: Clone + From<u64> + Into<u64> {}
 impl TxRole for u16 {}
 impl TxIndex for u64 {}
 
+/// Describes which kind of locking script the channel funding output uses
+/// and, therefore, which PSBT fields `TxGraph::render_cmt` must populate on
+/// the commitment transaction's funding input.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum FundingScript {
+    /// Legacy SegWit v0 2-of-2 multisig funding output.
+    Segwit,
+    /// Taproot (BOLT `simple-taproot-channel`) funding output, spent through
+    /// the MuSig2-aggregated key path.
+    Taproot(TaprootFunding),
+}
+
+impl Default for FundingScript {
+    fn default() -> Self { FundingScript::Segwit }
+}
+
+/// Key material backing a Taproot funding output, required to fill in the
+/// commitment input's `tap_internal_key` and `tap_key_origins` PSBT fields.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TaprootFunding {
+    /// MuSig2-aggregated internal key of the two channel participants,
+    /// spent via the key path since the funding output carries no script
+    /// path.
+    pub internal_key: XOnlyPublicKey,
+    /// Each participant's individual xonly key and BIP32 key source, used
+    /// to populate `tap_key_origins` so an external signer can recognize
+    /// which of its keys contributed to the aggregated `internal_key`.
+    pub participant_keys: Vec<(XOnlyPublicKey, KeySource)>,
+}
+
+/// Taproot leaf scripts backing a single HTLC output on the commitment
+/// transaction, used by `TxGraph::render_cmt_htlcs` to populate the
+/// `tap_internal_key`/`tap_scripts`/`tap_merkle_root` fields of the matching
+/// HTLC-timeout or HTLC-success input so it can later be signed along the
+/// script path.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct HtlcTapLeaves {
+    /// Internal key of the HTLC output's taproot output key (the revocation
+    /// pubkey, per BOLT-3).
+    pub internal_key: XOnlyPublicKey,
+    /// HTLC-timeout leaf script.
+    pub timeout_script: Script,
+    /// HTLC-success leaf script.
+    pub success_script: Script,
+}
+
+impl HtlcTapLeaves {
+    fn spend_info<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+    ) -> TaprootSpendInfo {
+        TaprootBuilder::new()
+            .add_leaf(1, self.timeout_script.clone())
+            .expect("two-leaf taproot tree always accepts depth-1 leaves")
+            .add_leaf(1, self.success_script.clone())
+            .expect("two-leaf taproot tree always accepts depth-1 leaves")
+            .finalize(secp, self.internal_key)
+            .expect("two-leaf tree with a valid internal key always finalizes")
+    }
+
+    /// The P2TR scriptPubKey committing to these exact leaves. `render_cmt`
+    /// derives the commitment output's script from this rather than
+    /// trusting a caller-supplied script, so the output actually paid to
+    /// and the leaves used to reconstruct the HTLC input's spend_info can
+    /// never drift apart.
+    fn output_script<C: Verification>(&self, secp: &Secp256k1<C>) -> Script {
+        Script::new_v1_p2tr_tweaked(self.spend_info(secp).output_key())
+    }
+}
+
+/// Value, in satoshis, of each anchor output in an anchor-output channel —
+/// small enough to be economical only when a fee bump is actually needed.
+pub const ANCHOR_OUTPUT_VALUE: u64 = 330;
+
+/// Identifies a channel counterparty, used to pick which anchor output
+/// `TxGraph::render_anchor_spend` builds a spending transaction for.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Party {
+    /// The local node.
+    Local,
+    /// The remote counterparty.
+    Remote,
+}
+
+/// Anchor-channel data: one CSV-gated anchor script per party, appended as
+/// extra commitment outputs so either side can CPFP-bump the commitment
+/// transaction's fee after a force-close.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct AnchorOutputs {
+    /// Witness script of the local party's anchor output: spendable
+    /// immediately by the local funding key, or by anyone after
+    /// `to_self_delay` confirmations.
+    pub local_anchor_script: Script,
+    /// Witness script of the remote party's anchor output, mirroring
+    /// `local_anchor_script` for the counterparty's key.
+    pub remote_anchor_script: Script,
+}
+
+impl AnchorOutputs {
+    fn script(&self, party: Party) -> &Script {
+        match party {
+            Party::Local => &self.local_anchor_script,
+            Party::Remote => &self.remote_anchor_script,
+        }
+    }
+}
+
+/// CLTV expiry and `graph` lookup key for an HTLC output, used to break
+/// BIP-69 ties against other HTLC outputs of equal amount and script and
+/// to let `render_cmt_htlcs` find the matching spending transaction.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct HtlcOutputMeta {
+    /// Index under which the spending `HtlcTimeout`/`HtlcSuccess` PSBT is
+    /// stored in `graph` (tried under both roles, since a given HTLC is
+    /// spent by exactly one of the two).
+    pub htlc_index: u64,
+    /// CLTV expiry of the HTLC, used only to break sort ties.
+    pub cltv_expiry: u32,
+}
+
+/// A single commitment-transaction output together with the BOLT-3
+/// metadata needed to place it deterministically (see
+/// `TxGraph::sorted_cmt_outs`) and, for HTLC outputs, to let
+/// `render_cmt_htlcs` find the spending transaction without relying on its
+/// position.
+#[derive(Clone, Eq, PartialEq)]
+pub struct CmtOutput {
+    pub output: psbt::Output,
+    /// `None` for to-local/to-remote outputs; `Some` for HTLC outputs.
+    pub htlc: Option<HtlcOutputMeta>,
+}
+
+impl CmtOutput {
+    /// A plain (non-HTLC) commitment output, e.g. to-local or to-remote.
+    pub fn plain(output: psbt::Output) -> Self { CmtOutput { output, htlc: None } }
+
+    /// An HTLC commitment output, matched back to its spending transaction
+    /// by `htlc_index` and ordered against other HTLC outputs by
+    /// `cltv_expiry`.
+    pub fn htlc(output: psbt::Output, htlc_index: u64, cltv_expiry: u32) -> Self {
+        CmtOutput {
+            output,
+            htlc: Some(HtlcOutputMeta { htlc_index, cltv_expiry }),
+        }
+    }
+}
+
+/// BOLT-3 commitment output ordering: BIP-69 (value, then scriptPubKey
+/// lexicographically), with ties between HTLC outputs of equal amount and
+/// script broken by ascending CLTV expiry. A free function (rather than a
+/// closure inline in `TxGraph::sorted_cmt_outs`) so it can be unit tested
+/// without a `TxGraph` instance.
+fn bip69_cmp(a: &CmtOutput, b: &CmtOutput) -> Ordering {
+    a.output
+        .amount
+        .cmp(&b.output.amount)
+        .then_with(|| a.output.script.cmp(&b.output.script))
+        .then_with(|| {
+            let a_cltv = a.htlc.map(|h| h.cltv_expiry).unwrap_or(0);
+            let b_cltv = b.htlc.map(|h| h.cltv_expiry).unwrap_or(0);
+            a_cltv.cmp(&b_cltv)
+        })
+}
+
+/// Sequence an HTLC-timeout/HTLC-success input should carry: an explicit
+/// BIP-68 CSV delay always wins; absent one, anchor channels default to
+/// `Sequence::ZERO` (CPFP-compatible) and non-anchor channels default to
+/// `Sequence::ENABLE_RBF_NO_LOCKTIME`, per the `cmt_htlc_csv` field doc. A
+/// free function so the fallback rules are unit testable without a
+/// `TxGraph` instance.
+fn htlc_sequence(explicit_csv: Option<Sequence>, anchors_enabled: bool) -> Sequence {
+    match (explicit_csv, anchors_enabled) {
+        (Some(csv), _) => csv,
+        (None, true) => Sequence::ZERO,
+        (None, false) => Sequence::ENABLE_RBF_NO_LOCKTIME,
+    }
+}
+
+/// Error returned by [`TxGraph::render_cmt_htlcs`] when an HTLC output has
+/// no matching spending transaction in `graph`.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum RenderError {
+    /// no `HtlcTimeout`/`HtlcSuccess` transaction found in `graph` for HTLC
+    /// index {0}.
+    NoMatchingHtlc(u64),
+}
+
 #[derive(Getters, Clone, Eq, PartialEq)]
 pub struct TxGraph<'channel> {
     /// Read-only data for extensions on the number of channel parties
     funding: &'channel Funding,
     pub cmt_version: i32,
-    pub cmt_locktime: u32,
-    pub cmt_sequence: u32,
-    pub cmt_outs: Vec<psbt::Output>,
+    pub cmt_locktime: PackedLockTime,
+    pub cmt_sequence: Sequence,
+    /// Commitment outputs in insertion order; `render_cmt` reorders these
+    /// per BOLT-3/BIP-69 before rendering (see `sorted_cmt_outs`).
+    pub cmt_outs: Vec<CmtOutput>,
+    /// Locking script used by the funding output; selects which PSBT
+    /// fields `render_cmt` fills in on the commitment input.
+    pub funding_script: FundingScript,
+    /// Taproot leaf scripts for HTLC outputs, keyed by the HTLC index (as
+    /// used to look up the corresponding `HtlcTimeout`/`HtlcSuccess`
+    /// transaction in `graph`). Empty for SegWit v0 channels.
+    pub cmt_htlc_taproot: BTreeMap<u64, HtlcTapLeaves>,
+    /// BIP-68 relative timelock (CSV delay) each HTLC-timeout/HTLC-success
+    /// input must carry in its `nSequence`, keyed by the HTLC index. Unset
+    /// entries fall back to `Sequence::ZERO` when `anchors` is set and to
+    /// `Sequence::ENABLE_RBF_NO_LOCKTIME` otherwise.
+    pub cmt_htlc_csv: BTreeMap<u64, Sequence>,
+    /// Anchor-channel data. `None` renders a plain commitment transaction
+    /// with no anchor outputs. When `Some`, `render_cmt` appends anchor
+    /// outputs and `render_cmt_htlcs` uses anchor-compatible HTLC
+    /// sequences, but `cmt_locktime`/`cmt_sequence` on the commitment
+    /// input itself are always taken verbatim, since they carry the
+    /// BOLT-3 obscured commitment number.
+    pub anchors: Option<AnchorOutputs>,
     graph: BTreeMap<u16, BTreeMap<u64, Psbt>>,
 }
 
@@ -49,9 +266,13 @@ where
             funding,
             // TODO: Check that we have commitment version set correctly
             cmt_version: 0,
-            cmt_locktime: 0,
-            cmt_sequence: 0,
+            cmt_locktime: PackedLockTime::ZERO,
+            cmt_sequence: Sequence::ZERO,
             cmt_outs: vec![],
+            funding_script: FundingScript::default(),
+            cmt_htlc_taproot: bmap! {},
+            cmt_htlc_csv: bmap! {},
+            anchors: None,
             graph: bmap! {},
         }
     }
@@ -120,51 +341,131 @@ where
         txes
     }
 
-    pub fn render_cmt_htlcs(&self) -> Vec<Psbt> {
+    /// Renders the commitment transaction followed by every HTLC
+    /// transaction that spends one of its outputs. Each HTLC output in
+    /// `sorted_cmt_outs` is matched to its spending transaction through the
+    /// `htlc_index` recorded in its `CmtOutput`, and the vout used is the
+    /// output's position after that sort; the spending transaction's
+    /// scriptPubKey and amount are not compared against the output. Fails
+    /// if an HTLC output has no matching `HtlcTimeout`/`HtlcSuccess` entry
+    /// in `graph`.
+    pub fn render_cmt_htlcs(&self) -> Result<Vec<Psbt>, RenderError> {
         let mut txes = Vec::with_capacity(self.len());
         let cmt_tx = self.render_cmt();
         txes.push(cmt_tx.clone());
 
         let txid = cmt_tx.to_txid();
-        for (index, _) in cmt_tx.outputs.clone().into_iter().enumerate() {
-            let htlc_index = index + 1;
-            if let Some(psbt) = self.tx(TxType::HtlcTimeout, htlc_index as u64)
-            {
-                let mut psbt = psbt.to_owned();
-                let prev =
-                    OutPoint::from_str(format!("{}:{}", txid, index).as_str())
-                        .expect("");
-                psbt.inputs[0].previous_outpoint = prev;
-                txes.push(psbt.to_owned());
-            }
+        for (vout, cmt_out) in self.sorted_cmt_outs().into_iter().enumerate() {
+            let Some(htlc) = cmt_out.htlc else {
+                continue;
+            };
+            let htlc_psbt = self
+                .tx(TxType::HtlcTimeout, htlc.htlc_index)
+                .or_else(|| self.tx(TxType::HtlcSuccess, htlc.htlc_index))
+                .ok_or(RenderError::NoMatchingHtlc(htlc.htlc_index))?;
+            let mut psbt = htlc_psbt.to_owned();
+            psbt.inputs[0].previous_outpoint = OutPoint::new(txid, vout as u32);
+            self.apply_htlc_extras(&mut psbt, htlc.htlc_index);
+            txes.push(psbt);
         }
+        Ok(txes)
+    }
 
-        for (index, _) in cmt_tx.outputs.clone().into_iter().enumerate() {
-            let htlc_index = index + 1;
-            if let Some(psbt) = self.tx(TxType::HtlcSuccess, htlc_index as u64)
-            {
-                let mut psbt = psbt.to_owned();
-                let prev =
-                    OutPoint::from_str(format!("{}:{}", txid, index).as_str())
-                        .expect("");
-                psbt.inputs[0].previous_outpoint = prev;
-                txes.push(psbt.to_owned());
-            }
+    /// Orders `cmt_outs` per BOLT-3: BIP-69 (value, then scriptPubKey
+    /// lexicographically), with ties between HTLC outputs of equal amount
+    /// and script broken by ascending CLTV expiry. This is the output
+    /// order `render_cmt` and `render_cmt_htlcs` actually render.
+    ///
+    /// For taproot HTLC outputs, the scriptPubKey is first overwritten with
+    /// the real, derived P2TR script from `cmt_htlc_taproot` rather than
+    /// whatever placeholder the caller stored in `cmt_outs`, and the sort
+    /// itself runs on that derived script — not the caller-supplied one.
+    /// Both channel parties must compute the identical commitment output
+    /// order (and hence the identical commitment txid) from the same
+    /// channel state, which is only guaranteed if ties are broken by a
+    /// script value the caller cannot get wrong or leave as a placeholder.
+    fn sorted_cmt_outs(&self) -> Vec<CmtOutput> {
+        let mut outs = self.cmt_outs.clone();
+        let secp = Secp256k1::verification_only();
+        for cmt_out in &mut outs {
+            let Some(htlc) = cmt_out.htlc else { continue };
+            let Some(tap) = self.cmt_htlc_taproot.get(&htlc.htlc_index) else {
+                continue;
+            };
+            cmt_out.output.script = tap.output_script(&secp).into();
         }
-        txes
+        outs.sort_by(bip69_cmp);
+        outs
+    }
+
+    /// Populates per-HTLC extras on the spending PSBT: the taproot
+    /// script-path fields (`tap_internal_key`, `tap_scripts`,
+    /// `tap_merkle_root`), if `htlc_index` has associated `HtlcTapLeaves`
+    /// (a no-op otherwise); and the input's `nSequence`, which is always
+    /// overwritten: with the BIP-68 CSV delay from `cmt_htlc_csv` if
+    /// `htlc_index` has an entry there, else with the `htlc_sequence`
+    /// fallback for the channel mode.
+    fn apply_htlc_extras(&self, psbt: &mut Psbt, htlc_index: u64) {
+        if let Some(tap) = self.cmt_htlc_taproot.get(&htlc_index) {
+            let secp = Secp256k1::verification_only();
+            let spend_info = tap.spend_info(&secp);
+            psbt.inputs[0].tap_internal_key = Some(tap.internal_key);
+            psbt.inputs[0].tap_merkle_root = spend_info.merkle_root();
+            psbt.inputs[0].tap_scripts = [
+                (tap.timeout_script.clone(), LeafVersion::TapScript),
+                (tap.success_script.clone(), LeafVersion::TapScript),
+            ]
+            .into_iter()
+            .filter_map(|leaf| {
+                spend_info
+                    .control_block(&leaf)
+                    .map(|control_block| (control_block, leaf))
+            })
+            .collect();
+        }
+        let explicit_csv = self.cmt_htlc_csv.get(&htlc_index).copied();
+        psbt.unsigned_tx.input[0].sequence =
+            htlc_sequence(explicit_csv, self.anchors.is_some());
     }
 
     pub fn render_cmt(&self) -> Psbt {
+        let mut cmt_outs = self.sorted_cmt_outs();
+        let mut tx_outs = vec![default!(); cmt_outs.len()];
+        // Anchor-channel mode: both to-local and to-remote get a small,
+        // immediately-spendable anchor output so either party can CPFP the
+        // commitment transaction after a force-close. Anchors are appended
+        // after the BOLT-3 sort, since they carry no economic priority to
+        // order against.
+        if let Some(anchors) = &self.anchors {
+            for party in [Party::Local, Party::Remote] {
+                let script_pubkey = anchors.script(party).to_v0_p2wsh();
+                tx_outs.push(TxOut {
+                    value: ANCHOR_OUTPUT_VALUE,
+                    script_pubkey: script_pubkey.clone(),
+                });
+                cmt_outs.push(CmtOutput::plain(psbt::Output {
+                    amount: ANCHOR_OUTPUT_VALUE,
+                    script: script_pubkey.into(),
+                    witness_script: Some(anchors.script(party).clone()),
+                    ..default!()
+                }));
+            }
+        }
         let cmt_tx = Transaction {
             version: self.cmt_version,
-            lock_time: bitcoin::PackedLockTime(self.cmt_locktime),
+            // `cmt_locktime`/`cmt_sequence` carry the BOLT-3 obscured
+            // commitment-number encoding (see chunk0-3); anchor outputs
+            // never touch the commitment transaction's own nLockTime or
+            // nSequence; only second-stage HTLC transactions get
+            // anchor-specific sequences (see `apply_htlc_extras`).
+            lock_time: self.cmt_locktime,
             input: vec![TxIn {
                 previous_output: self.funding.outpoint(),
                 script_sig: empty!(),
-                sequence: bitcoin::Sequence(self.cmt_sequence),
+                sequence: self.cmt_sequence,
                 witness: empty!(),
             }],
-            output: vec![default!(); self.cmt_outs.len()],
+            output: tx_outs,
         };
         let mut psbt = Psbt::with(cmt_tx, PsbtVersion::V0).expect(
             "PSBT construction fails only if script_sig and witness are not \
@@ -177,15 +478,117 @@ where
             value: funding_output.amount,
             script_pubkey: funding_output.script.clone().into(),
         });
-        psbt.inputs[0].witness_script = funding_output.witness_script.clone();
-        psbt.inputs[0].bip32_derivation =
-            funding_output.bip32_derivation.clone();
+        match &self.funding_script {
+            FundingScript::Segwit => {
+                psbt.inputs[0].witness_script =
+                    funding_output.witness_script.clone();
+                psbt.inputs[0].bip32_derivation =
+                    funding_output.bip32_derivation.clone();
+            }
+            FundingScript::Taproot(taproot) => {
+                psbt.inputs[0].tap_internal_key = Some(taproot.internal_key);
+                // Key-path-only funding output: there is no script tree.
+                psbt.inputs[0].tap_merkle_root = None;
+                psbt.inputs[0].tap_key_origins = taproot
+                    .participant_keys
+                    .iter()
+                    .map(|(key, source)| (*key, (vec![], source.clone())))
+                    .collect();
+            }
+        }
         for (index, output) in psbt.outputs.iter_mut().enumerate() {
-            *output = self.cmt_outs[index].clone();
+            *output = cmt_outs[index].output.clone();
         }
         psbt
     }
 
+    /// Builds a child transaction spending `party`'s anchor output on the
+    /// commitment transaction together with `wallet_input` (an arbitrary
+    /// additional UTXO supplying fees), paying to `wallet_output`. Returns
+    /// `None` if the graph was not configured for anchor outputs. The
+    /// returned PSBT is ready to hand to a fee estimator and, afterwards,
+    /// to a [`Signer`].
+    pub fn render_anchor_spend(
+        &self,
+        party: Party,
+        wallet_input: TxIn,
+        wallet_output: TxOut,
+    ) -> Option<Psbt> {
+        let anchors = self.anchors.as_ref()?;
+        let cmt_psbt = self.render_cmt();
+        let cmt_txid = cmt_psbt.to_txid();
+        let anchor_vout = match party {
+            Party::Local => cmt_psbt.outputs.len() - 2,
+            Party::Remote => cmt_psbt.outputs.len() - 1,
+        };
+        let anchor_script = anchors.script(party).clone();
+        let anchor_input = TxIn {
+            previous_output: OutPoint::new(cmt_txid, anchor_vout as u32),
+            script_sig: empty!(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: empty!(),
+        };
+        let spend_tx = Transaction {
+            version: 2,
+            lock_time: PackedLockTime::ZERO,
+            input: vec![anchor_input, wallet_input],
+            output: vec![wallet_output],
+        };
+        let mut psbt = Psbt::with(spend_tx, PsbtVersion::V0).expect(
+            "PSBT construction fails only if script_sig and witness are not \
+             empty; which is not the case here",
+        );
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: ANCHOR_OUTPUT_VALUE,
+            script_pubkey: anchor_script.to_v0_p2wsh(),
+        });
+        psbt.inputs[0].witness_script = Some(anchor_script);
+        Some(psbt)
+    }
+
+    /// Renders the commitment transaction and every HTLC transaction that
+    /// spends it (via `render_cmt_htlcs`, so HTLC inputs carry their real
+    /// `previous_outpoint`, taproot fields and CSV sequence) and hands the
+    /// result to an out-of-process `signer`, folding back every partial
+    /// (or taproot) signature it returns into the matching entry of
+    /// `graph`, keyed by `(txid, input)`.
+    ///
+    /// The commitment transaction itself is re-synthesized on every
+    /// `render_cmt` call and has no mutable storage slot in `graph`, so its
+    /// signature cannot be folded back the same way as the HTLC
+    /// transactions' — it is returned directly instead, alongside the
+    /// number of inputs the signer reported as signed across the whole
+    /// graph.
+    pub fn sign_with(
+        &mut self,
+        signer: &impl Signer,
+    ) -> Result<(Psbt, usize), SignError> {
+        let mut txes = self.render_cmt_htlcs()?;
+        let signed = signer.sign_graph(&mut txes)?;
+        let mut txes = txes.into_iter();
+        let signed_cmt = txes.next().expect(
+            "render_cmt_htlcs always pushes the commitment PSBT first",
+        );
+        for signed_psbt in txes {
+            let txid = signed_psbt.to_txid();
+            for (_, _, graph_psbt) in self.vec_mut() {
+                if graph_psbt.to_txid() != txid {
+                    continue;
+                }
+                for (input, signed_input) in
+                    graph_psbt.inputs.iter_mut().zip(&signed_psbt.inputs)
+                {
+                    input.partial_sigs.extend(signed_input.partial_sigs.clone());
+                    input.tap_key_sig = input.tap_key_sig.or(signed_input.tap_key_sig);
+                    input
+                        .tap_script_sigs
+                        .extend(signed_input.tap_script_sigs.clone());
+                }
+            }
+        }
+        Ok((signed_cmt, signed))
+    }
+
     pub fn iter(&self) -> GraphIter {
         GraphIter::with(self)
     }
@@ -231,3 +634,195 @@ impl<'iter, 'channel> Iterator for GraphIter<'iter, 'channel> {
         tx.map(|tx| (self.curr_role, self.curr_index, tx))
     }
 }
+
+/// Error returned by a [`Signer`] while signing the PSBTs produced by
+/// [`TxGraph::render`], [`TxGraph::render_cmt`] or
+/// [`TxGraph::render_cmt_htlcs`].
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum SignError {
+    /// external signer produced no signature for input {1} of transaction
+    /// {0}.
+    NoSignature(Txid, usize),
+    /// communication with the external signer failed: {0}
+    Transport(String),
+    /// external signer rejected the signing request: {0}
+    Rejected(String),
+    /// rendering the transactions to sign failed: {0}
+    Render(RenderError),
+}
+
+impl From<RenderError> for SignError {
+    fn from(err: RenderError) -> Self { SignError::Render(err) }
+}
+
+/// Out-of-process signer for the PSBTs a [`TxGraph`] renders. Implementing
+/// this trait lets a node route channel transactions to a hardware wallet
+/// or any other external key custodian instead of holding private keys
+/// itself.
+pub trait Signer {
+    /// Signs as many inputs across `txes` as this signer holds keys for,
+    /// filling partial (or taproot key/script-path) signatures into the
+    /// PSBTs in place, and returns the number of inputs signed.
+    fn sign_graph(&self, txes: &mut [Psbt]) -> Result<usize, SignError>;
+}
+
+/// [`Signer`] that delegates signing to an external device speaking the
+/// Hardware Wallet Interface (HWI) JSON protocol over stdio/USB, the same
+/// integration path BDK uses to wire Ledger/Trezor devices into its wallet
+/// signing flow. This keeps channel private keys off the node entirely.
+pub struct HwiSigner {
+    /// Path to the `hwi` executable (or a wrapper script implementing its
+    /// command-line JSON protocol).
+    pub hwi_path: String,
+    /// Master-key fingerprint identifying which attached device should
+    /// sign, as reported by `hwi enumerate`.
+    pub fingerprint: String,
+}
+
+impl HwiSigner {
+    pub fn new(
+        hwi_path: impl Into<String>,
+        fingerprint: impl Into<String>,
+    ) -> Self {
+        HwiSigner {
+            hwi_path: hwi_path.into(),
+            fingerprint: fingerprint.into(),
+        }
+    }
+
+    fn sign_one(&self, psbt: &Psbt) -> Result<Psbt, SignError> {
+        let output = Command::new(&self.hwi_path)
+            .args(["--fingerprint", &self.fingerprint, "signtx", &psbt.to_string()])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|err| SignError::Transport(err.to_string()))?;
+        if !output.status.success() {
+            return Err(SignError::Rejected(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
+        let reply: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|err| SignError::Transport(err.to_string()))?;
+        let signed_b64 =
+            reply.get("psbt").and_then(|value| value.as_str()).ok_or_else(
+                || {
+                    SignError::Transport(
+                        "HWI reply did not contain a `psbt` field".to_owned(),
+                    )
+                },
+            )?;
+        Psbt::from_str(signed_b64)
+            .map_err(|err| SignError::Transport(err.to_string()))
+    }
+}
+
+impl Signer for HwiSigner {
+    fn sign_graph(&self, txes: &mut [Psbt]) -> Result<usize, SignError> {
+        let mut signed = 0usize;
+        for psbt in txes.iter_mut() {
+            let signed_psbt = self.sign_one(psbt)?;
+            // The device signs only the inputs it holds keys for, so count
+            // the inputs that gained a signature in this call.
+            signed += psbt
+                .inputs
+                .iter()
+                .zip(&signed_psbt.inputs)
+                .filter(|(before, after)| {
+                    after.partial_sigs.len() > before.partial_sigs.len()
+                        || (before.tap_key_sig.is_none()
+                            && after.tap_key_sig.is_some())
+                        || after.tap_script_sigs.len()
+                            > before.tap_script_sigs.len()
+                })
+                .count();
+            *psbt = signed_psbt;
+        }
+        Ok(signed)
+    }
+}
+
+// `TxGraph` itself is constructed from an external `&'channel Funding`,
+// which this module does not own and cannot build in isolation, so these
+// tests exercise the free functions pulled out of `render_cmt`/
+// `render_cmt_htlcs`/`apply_htlc_extras` for exactly that reason: BIP-69
+// ordering, the resulting HTLC-to-vout mapping, and the HTLC sequence
+// fallback rules are all plain data transformations that don't need a
+// `Funding` to be meaningfully tested.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn out(amount: u64, script_byte: u8, id: u64, cltv_expiry: u32) -> CmtOutput {
+        CmtOutput {
+            output: psbt::Output {
+                amount,
+                script: Script::from(vec![script_byte]).into(),
+                ..default!()
+            },
+            htlc: Some(HtlcOutputMeta {
+                htlc_index: id,
+                cltv_expiry,
+            }),
+        }
+    }
+
+    #[test]
+    fn bip69_orders_by_amount_first() {
+        let mut outs = vec![out(200, 0x01, 1, 0), out(100, 0x01, 2, 0)];
+        outs.sort_by(bip69_cmp);
+        let order: Vec<_> =
+            outs.iter().map(|o| o.htlc.unwrap().htlc_index).collect();
+        assert_eq!(order, vec![2, 1]);
+    }
+
+    #[test]
+    fn bip69_breaks_amount_ties_by_script() {
+        let mut outs = vec![out(100, 0x02, 1, 0), out(100, 0x01, 2, 0)];
+        outs.sort_by(bip69_cmp);
+        let order: Vec<_> =
+            outs.iter().map(|o| o.htlc.unwrap().htlc_index).collect();
+        assert_eq!(order, vec![2, 1]);
+    }
+
+    #[test]
+    fn bip69_breaks_equal_output_ties_by_cltv_expiry() {
+        let mut outs = vec![out(100, 0x01, 1, 600_000), out(100, 0x01, 2, 500_000)];
+        outs.sort_by(bip69_cmp);
+        let order: Vec<_> =
+            outs.iter().map(|o| o.htlc.unwrap().htlc_index).collect();
+        assert_eq!(order, vec![2, 1]);
+    }
+
+    #[test]
+    fn sorted_order_determines_htlc_vout_mapping() {
+        // Mirrors what `render_cmt_htlcs` relies on: after the BIP-69
+        // sort, enumerating the outputs gives the real vout each HTLC
+        // will land at, independent of insertion order.
+        let to_local = out(500_000, 0x00, 99, 0);
+        let htlc_a = out(1_000, 0x02, 7, 100);
+        let htlc_b = out(1_000, 0x01, 3, 100);
+        let mut outs = vec![htlc_a, to_local, htlc_b];
+        outs.sort_by(bip69_cmp);
+        let vouts: Vec<_> = outs
+            .iter()
+            .enumerate()
+            .map(|(vout, o)| (vout, o.htlc.unwrap().htlc_index))
+            .collect();
+        assert_eq!(vouts, vec![(0, 3), (1, 7), (2, 99)]);
+    }
+
+    #[test]
+    fn htlc_sequence_prefers_explicit_csv_regardless_of_anchors() {
+        let csv = Sequence(144);
+        assert_eq!(htlc_sequence(Some(csv), true), csv);
+        assert_eq!(htlc_sequence(Some(csv), false), csv);
+    }
+
+    #[test]
+    fn htlc_sequence_falls_back_per_channel_mode() {
+        assert_eq!(htlc_sequence(None, true), Sequence::ZERO);
+        assert_eq!(htlc_sequence(None, false), Sequence::ENABLE_RBF_NO_LOCKTIME);
+    }
+}